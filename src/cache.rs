@@ -1,12 +1,59 @@
+use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 
 use crate::item::Item;
 use std::hash::Hash;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Lets a value report its own expiry, for caches where a single `Duration` can't capture the
+/// rule — a JWT with an embedded `exp`, a signed blob, or a record invalidated by an external
+/// version bump. See [`Cache::with_value_expiry`].
+pub trait CanExpire {
+    /// Returns true if the value itself should be treated as expired, independent of the
+    /// cache's time-based expiry.
+    fn is_expired(&self) -> bool;
+}
+
+/// Callback invoked with the key, value and [`EvictionCause`] of an entry leaving the cache. See
+/// [`Cache::with_eviction_listener`].
+pub type EvictionListener<T, V> = Box<dyn Fn(&T, V, EvictionCause) + Send + Sync>;
+
+/// The reason an entry left the cache, reported to a listener registered via
+/// [`Cache::with_eviction_listener`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionCause {
+    /// The entry's time-based or value-based expiry elapsed.
+    Expired,
+    /// `set` was called again for a key that already held a value.
+    Replaced,
+    /// `remove` was called for this key.
+    Removed,
+    /// The cache was constructed with a capacity bound and this entry was the
+    /// least-recently-used victim.
+    Capacity,
+    /// `clear` removed every entry in the cache.
+    Cleared,
+}
 
 pub struct Cache<T, V> {
     items: DashMap<T, Item<V>>,
     item_duration: Option<Duration>,
+    max_items: Option<usize>,
+    access_counter: AtomicU64,
+    sliding: bool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    value_expiry: Option<fn(&V) -> bool>,
+    listener: Option<EvictionListener<T, V>>,
+    // Expiry wheel: keys due to expire are bucketed by the second they expire in (relative to
+    // `start`), so `remove_expired` only has to pop the buckets that are due rather than scan
+    // every entry. Only time-based expiry is bucketed here; see `remove_expired`.
+    buckets: DashMap<u64, Vec<T>>,
+    start: Instant,
 }
 
 impl<T, V> Cache<T, V>
@@ -20,71 +67,413 @@ where
     /// # Example
     ///
     /// ```rust
-    /// use async_std::sync::Arc;
-    /// use async_std::task;
     /// use r_cache::cache::Cache;
+    /// use std::sync::Arc;
     /// use std::time::Duration;
     ///
     /// const KEY: i8 = 0;
     /// const VALUE: &str = "VALUE";
     ///
-    /// #[async_std::main]
-    /// async fn main() {
-    ///     let cache = Arc::new(Cache::new(Some(Duration::from_secs(5 * 60))));
-    ///     task::spawn({
-    ///         let cache = Arc::clone(&cache);
-    ///         async move {
-    ///             loop {
-    ///                 task::sleep(Duration::from_secs(10 * 60)).await;
-    ///                 cache.remove_expired();
-    ///             }
-    ///         }
-    ///     });
+    /// let cache = Arc::new(Cache::new(Some(Duration::from_secs(5 * 60))));
+    /// Cache::spawn_janitor(Arc::clone(&cache), Duration::from_secs(10 * 60));
     ///
-    ///     cache.set(KEY, VALUE, None);
+    /// cache.set(KEY, VALUE, None);
     ///
-    ///     assert_eq!(VALUE, cache.get(&KEY).unwrap())
-    /// }
+    /// assert_eq!(VALUE, cache.get(&KEY).unwrap())
     /// ```
     pub fn new(item_duration: Option<Duration>) -> Self {
+        Self::new_with_capacity(item_duration, None)
+    }
+
+    /// Construct a new `Cache` with sliding expiration: a successful `get` or `get_with_expiry`
+    /// resets the item's expiry to its duration (the custom duration it was `set` with, or else
+    /// `item_duration`) from the time of access, rather than letting
+    /// it lapse from insertion time. Useful for idle-timeout sessions where frequently-touched
+    /// keys should stay alive and abandoned ones should expire.
+    pub fn with_sliding_duration(item_duration: Duration) -> Self {
+        Self {
+            sliding: true,
+            ..Self::new_with_capacity(Some(item_duration), None)
+        }
+    }
+
+    /// Construct a new `Cache` bounded to at most `max_items` entries and no default expiry.
+    /// Once a `set` would push the cache past `max_items`, the least-recently-used entry is
+    /// evicted to make room.
+    ///
+    /// Eviction is best-effort under concurrency: a `set` racing with another thread's `set`
+    /// may transiently leave the cache slightly over `max_items` until the next insert.
+    pub fn with_capacity(max_items: usize) -> Self {
+        Self::new_with_capacity(None, Some(max_items))
+    }
+
+    /// Construct a new `Cache` bounded to at most `max_items` entries, with a default item
+    /// expiration time. See [`Cache::with_capacity`] for eviction semantics.
+    pub fn with_capacity_and_duration(max_items: usize, item_duration: Option<Duration>) -> Self {
+        Self::new_with_capacity(item_duration, Some(max_items))
+    }
+
+    fn new_with_capacity(item_duration: Option<Duration>, max_items: Option<usize>) -> Self {
         Cache {
             items: DashMap::new(),
             item_duration,
+            max_items,
+            access_counter: AtomicU64::new(0),
+            sliding: false,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            value_expiry: None,
+            listener: None,
+            buckets: DashMap::new(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Construct a new `Cache` that invokes `listener` whenever an entry leaves the cache —
+    /// expired, replaced by a new `set`, explicitly `remove`d, evicted for capacity, or dropped
+    /// by `clear` — so callers can flush dependent state, close handles, or log. The listener
+    /// receives the evicted key, the evicted value, and the [`EvictionCause`].
+    pub fn with_eviction_listener(
+        item_duration: Option<Duration>,
+        listener: EvictionListener<T, V>,
+    ) -> Self {
+        Self {
+            listener: Some(listener),
+            ..Self::new_with_capacity(item_duration, None)
+        }
+    }
+
+    /// Construct a new `Cache` bounded to at most `max_items` entries that also invokes
+    /// `listener` whenever an entry leaves the cache. Combines [`Cache::with_capacity`] and
+    /// [`Cache::with_eviction_listener`] — in particular, this is the only way to observe
+    /// [`EvictionCause::Capacity`], since plain [`Cache::with_eviction_listener`] never bounds
+    /// capacity.
+    pub fn with_capacity_and_listener(
+        max_items: usize,
+        item_duration: Option<Duration>,
+        listener: EvictionListener<T, V>,
+    ) -> Self {
+        Self {
+            listener: Some(listener),
+            ..Self::new_with_capacity(item_duration, Some(max_items))
+        }
+    }
+
+    // Invokes the eviction listener, if one is registered.
+    fn notify(&self, key: &T, value: V, cause: EvictionCause) {
+        if let Some(listener) = &self.listener {
+            listener(key, value, cause);
         }
     }
 
+    /// Construct a new `Cache` that also treats an item as expired when its value reports
+    /// itself expired via [`CanExpire::is_expired`], in addition to the time-based
+    /// `item_duration`. Opt-in: callers whose `V` does not implement `CanExpire` keep using
+    /// `Cache::new` and are unaffected.
+    pub fn with_value_expiry(item_duration: Option<Duration>) -> Self
+    where
+        V: CanExpire,
+    {
+        fn check_expiry<V: CanExpire>(value: &V) -> bool {
+            value.is_expired()
+        }
+
+        Self {
+            value_expiry: Some(check_expiry::<V>),
+            ..Self::new_with_capacity(item_duration, None)
+        }
+    }
+
+    // Returns the next tick of the monotonic access counter, used to track recency of use.
+    fn next_tick(&self) -> u64 {
+        self.access_counter.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // Returns true if `item` has expired by time, or — for a cache constructed with
+    // `with_value_expiry` — by its own value-based rule.
+    fn is_expired(&self, item: &Item<V>) -> bool {
+        item.expired() || self.value_expiry.is_some_and(|is_expired| is_expired(&item.object))
+    }
+
     /// Get a cache item associated with a given key.
     pub fn get(&self, key: &T) -> Option<V>
     where
-        T: Eq + Hash,
+        T: Eq + Hash + Clone,
         V: Clone,
     {
-        self.items
+        let value = self
+            .items
             .get(key)
-            .filter(|item| !item.expired())
-            .map(|item| item.object.clone())
+            .filter(|item| !self.is_expired(item))
+            .map(|item| {
+                item.touch(self.next_tick());
+                if self.sliding {
+                    item.slide_expiry();
+                    if let Some(expiry) = item.expiry() {
+                        self.register_bucket(key.clone(), expiry);
+                    }
+                }
+                item.object.clone()
+            });
+
+        if value.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        value
+    }
+
+    /// Get a cache item and the absolute instant it will expire at, if present, not expired,
+    /// and configured to expire at all.
+    ///
+    /// Like `get`, this refreshes recency and, for a cache constructed with
+    /// [`Cache::with_sliding_duration`], resets the item's expiry before it is reported.
+    pub fn get_with_expiry(&self, key: &T) -> Option<(V, Instant)>
+    where
+        T: Eq + Hash + Clone,
+        V: Clone,
+    {
+        let item = self.items.get(key).filter(|item| !self.is_expired(item))?;
+        item.touch(self.next_tick());
+        if self.sliding {
+            item.slide_expiry();
+        }
+
+        let expiry = item.expiry()?;
+        if self.sliding {
+            self.register_bucket(key.clone(), expiry);
+        }
+
+        Some((item.object.clone(), expiry))
     }
 
     /// Set an item in the cache with an associated key.
     /// The item will have the default cache expiration time if custom duration of `None` is given.
+    /// If the cache was constructed with a capacity and this insert pushes it over that limit,
+    /// the least-recently-used entry is evicted.
     pub fn set(&self, key: T, value: V, custom_duration: Option<Duration>) -> Option<V>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let tick = self.next_tick();
+        let notify_key = self.listener.is_some().then(|| key.clone());
+        let resolved_duration = custom_duration.or(self.item_duration);
+        let bucket_key = resolved_duration.map(|_| key.clone());
+
+        let item = Item::new(value, resolved_duration, tick);
+        let expiry = item.expiry();
+        let previous = self.items.insert(key, item).map(|item| item.object);
+
+        if let (Some(key), Some(expiry)) = (bucket_key, expiry) {
+            self.register_bucket(key, expiry);
+        }
+
+        if let (Some(previous), Some(key)) = (&previous, &notify_key) {
+            self.notify(key, previous.clone(), EvictionCause::Replaced);
+        }
+
+        if let Some(max_items) = self.max_items {
+            self.evict_over_capacity(max_items);
+        }
+
+        previous
+    }
+
+    // Returns the expiry wheel bucket `expiry` falls into: the second it expires in, relative
+    // to the cache's construction.
+    fn bucket_for(&self, expiry: Instant) -> u64 {
+        expiry.saturating_duration_since(self.start).as_secs()
+    }
+
+    // Registers `key` in the bucket for `expiry`, so `remove_expired` can find it without
+    // scanning the whole store.
+    fn register_bucket(&self, key: T, expiry: Instant)
     where
         T: Eq + Hash,
     {
-        self.items
-            .insert(
-                key,
-                Item::new(value, custom_duration.or(self.item_duration)),
-            )
-            .map(|item| item.object)
+        self.buckets.entry(self.bucket_for(expiry)).or_default().push(key);
     }
 
-    /// Remove all expired items from the cache.
+    // Evicts the least-recently-used entry/entries until the cache is within `max_items`.
+    fn evict_over_capacity(&self, max_items: usize)
+    where
+        T: Eq + Hash + Clone,
+    {
+        while self.items.len() > max_items {
+            let lru_key = self
+                .items
+                .iter()
+                .min_by_key(|entry| entry.value().last_access())
+                .map(|entry| entry.key().clone());
+
+            match lru_key {
+                Some(key) => {
+                    if let Some((key, item)) = self.items.remove(&key) {
+                        self.evictions.fetch_add(1, Ordering::Relaxed);
+                        self.notify(&key, item.object, EvictionCause::Capacity);
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Get the item associated with `key`, or compute, store and return one via `init` if it is
+    /// absent or expired. An expired entry displaced this way notifies the eviction listener
+    /// with [`EvictionCause::Expired`] and counts toward `evictions`, the same as `remove_expired`.
+    ///
+    /// Concurrent callers that miss on the same `key` do not all run `init`: the per-shard write
+    /// lock is held across the check-and-insert, so only the caller that wins the race computes
+    /// the value while the rest observe the freshly inserted entry.
+    pub fn get_or_insert_with<F>(&self, key: T, init: F, custom_duration: Option<Duration>) -> V
+    where
+        T: Eq + Hash + Clone,
+        F: FnOnce() -> V,
+    {
+        let tick = self.next_tick();
+        match self.items.entry(key) {
+            Entry::Occupied(entry) if !self.is_expired(entry.get()) => {
+                entry.get().touch(tick);
+                entry.get().object.clone()
+            }
+            entry => {
+                let value = init();
+                let item = Item::new(value.clone(), custom_duration.or(self.item_duration), tick);
+                let expiry = item.expiry();
+                let key = entry.key().clone();
+                match entry {
+                    Entry::Occupied(mut o) => {
+                        let displaced = o.insert(item).object;
+                        self.evictions.fetch_add(1, Ordering::Relaxed);
+                        self.notify(&key, displaced, EvictionCause::Expired);
+                    }
+                    Entry::Vacant(v) => drop(v.insert(item)),
+                }
+                if let Some(expiry) = expiry {
+                    self.register_bucket(key, expiry);
+                }
+                if let Some(max_items) = self.max_items {
+                    self.evict_over_capacity(max_items);
+                }
+                value
+            }
+        }
+    }
+
+    /// Fallible variant of [`Cache::get_or_insert_with`]: `init` may fail, in which case the
+    /// cache is left untouched and the error is returned to the caller.
+    pub fn try_get_or_insert_with<F, E>(
+        &self,
+        key: T,
+        init: F,
+        custom_duration: Option<Duration>,
+    ) -> Result<V, E>
+    where
+        T: Eq + Hash + Clone,
+        F: FnOnce() -> Result<V, E>,
+    {
+        let tick = self.next_tick();
+        match self.items.entry(key) {
+            Entry::Occupied(entry) if !self.is_expired(entry.get()) => {
+                entry.get().touch(tick);
+                Ok(entry.get().object.clone())
+            }
+            entry => {
+                let value = init()?;
+                let item = Item::new(value.clone(), custom_duration.or(self.item_duration), tick);
+                let expiry = item.expiry();
+                let key = entry.key().clone();
+                match entry {
+                    Entry::Occupied(mut o) => {
+                        let displaced = o.insert(item).object;
+                        self.evictions.fetch_add(1, Ordering::Relaxed);
+                        self.notify(&key, displaced, EvictionCause::Expired);
+                    }
+                    Entry::Vacant(v) => drop(v.insert(item)),
+                }
+                if let Some(expiry) = expiry {
+                    self.register_bucket(key, expiry);
+                }
+                if let Some(max_items) = self.max_items {
+                    self.evict_over_capacity(max_items);
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Remove expired items from the cache.
+    ///
+    /// This only has to pop the expiry-wheel buckets that are due rather than scan every
+    /// entry, so it is cheap to call often. A key found in a due bucket is checked against its
+    /// live item before removal, since the item may have been overwritten with a new expiry
+    /// since the bucket entry was registered; if it is not actually expired yet, it is
+    /// re-registered under its live expiry rather than dropped, so it is never lost to the
+    /// wheel. Items whose only expiry signal is [`CanExpire`] (no time-based duration) are never
+    /// bucketed, so they are swept with a fallback scan instead — see below.
     pub fn remove_expired(&self)
     where
         T: Eq + Hash + Clone,
     {
-        self.items.retain(|_, item| !item.expired());
+        let now_bucket = self.bucket_for(Instant::now());
+        let due_buckets: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|bucket| *bucket <= now_bucket)
+            .collect();
+
+        for bucket in due_buckets {
+            let keys = match self.buckets.remove(&bucket) {
+                Some((_, keys)) => keys,
+                None => continue,
+            };
+
+            for key in keys {
+                let live = self
+                    .items
+                    .get(&key)
+                    .map(|item| (self.is_expired(&item), item.expiry()));
+
+                match live {
+                    Some((true, _)) => {
+                        if let Some((key, item)) = self.items.remove(&key) {
+                            self.evictions.fetch_add(1, Ordering::Relaxed);
+                            self.notify(&key, item.object, EvictionCause::Expired);
+                        }
+                    }
+                    // Not actually expired yet — the item was re-`set` with a later expiry (or
+                    // this bucket fired a touch early). Re-register it under its live expiry
+                    // instead of dropping it, or it would never be swept again.
+                    Some((false, Some(expiry))) => self.register_bucket(key, expiry),
+                    Some((false, None)) | None => {}
+                }
+            }
+        }
+
+        // A value reporting itself expired via `CanExpire` isn't bucketed — there's no time
+        // deadline to key a bucket on, and even when the cache also has a time-based duration
+        // its bucket won't be due yet. So a cache constructed with `with_value_expiry` needs a
+        // plain scan to proactively reclaim entries only `CanExpire` currently flags.
+        if self.value_expiry.is_some() {
+            let value_expired_keys: Vec<T> = self
+                .items
+                .iter()
+                .filter(|entry| self.is_expired(entry.value()))
+                .map(|entry| entry.key().clone())
+                .collect();
+
+            for key in value_expired_keys {
+                if let Some((key, item)) = self.items.remove(&key) {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                    self.notify(&key, item.object, EvictionCause::Expired);
+                }
+            }
+        }
+
         self.shrink();
     }
 
@@ -94,14 +483,38 @@ where
         T: Eq + Hash,
     {
         let item = self.items.remove(key).map(|(_, item)| item.object);
+
+        if let Some(value) = &item {
+            self.notify(key, value.clone(), EvictionCause::Removed);
+        }
+
         self.shrink();
 
         item
     }
 
     /// Clear the entire cache of all items regardless of expiry times.
-    pub fn clear(&self) {
-        self.items.clear();
+    pub fn clear(&self)
+    where
+        T: Eq + Hash + Clone,
+    {
+        if self.listener.is_some() {
+            let cleared: Vec<(T, V)> = self
+                .items
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().object.clone()))
+                .collect();
+
+            self.items.clear();
+
+            for (key, value) in cleared {
+                self.notify(&key, value, EvictionCause::Cleared);
+            }
+        } else {
+            self.items.clear();
+        }
+
+        self.buckets.clear();
         self.shrink();
     }
 
@@ -110,13 +523,76 @@ where
     where
         T: Eq + Hash,
     {
-        self.items.shrink_to_fit()
+        self.items.shrink_to_fit();
+        self.buckets.shrink_to_fit();
+    }
+
+    /// The number of items currently held in the cache, including any not yet swept by
+    /// `remove_expired`.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the cache holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The number of `get` calls that found a non-expired value.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of `get` calls that found no value, or only an expired one.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// The number of items removed via capacity or expiry eviction.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// The ratio of hits to total `get` calls, or `0.0` if `get` has never been called.
+    pub fn hit_ratio(&self) -> f64 {
+        let hits = self.hits() as f64;
+        let misses = self.misses() as f64;
+        let total = hits + misses;
+
+        if total == 0.0 {
+            0.0
+        } else {
+            hits / total
+        }
+    }
+
+    /// Reset the hit, miss and eviction counters to zero.
+    pub fn reset_metrics(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+
+    /// Spawn a background thread that calls `remove_expired` on `cache` every `interval`, so
+    /// callers don't have to hand-roll their own sweep loop.
+    pub fn spawn_janitor(cache: Arc<Self>, interval: Duration) -> JoinHandle<()>
+    where
+        T: Eq + Hash + Clone + Send + Sync + 'static,
+        V: Clone + Send + Sync + 'static,
+    {
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            cache.remove_expired();
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::cache::Cache;
+    use crate::cache::{Cache, EvictionCause};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
     use std::time::Duration;
 
     const KEY: i8 = 0;
@@ -212,7 +688,7 @@ mod tests {
     fn remove_remove_expired_item() {
         let cache = Cache::new(Some(Duration::from_secs(2)));
         cache.set(KEY, VALUE, None);
-        if let None = cache.remove(&KEY) {
+        if cache.remove(&KEY).is_none() {
             panic!("none returned from removing existing value")
         };
         if cache.items.get(&KEY).is_some() {
@@ -223,8 +699,392 @@ mod tests {
     #[test]
     fn remove_return_none_if_not_found() {
         let cache: Cache<i8, &str> = Cache::new(Some(Duration::from_secs(2)));
-        if let Some(_) = cache.remove(&KEY) {
+        if cache.remove(&KEY).is_some() {
             panic!("some value was returned from remove")
         };
     }
+
+    #[test]
+    fn set_past_capacity_evicts_least_recently_used() {
+        let cache = Cache::with_capacity(2);
+        cache.set(1, "one", None);
+        cache.set(2, "two", None);
+        cache.set(3, "three", None);
+
+        assert_eq!(cache.items.len(), 2);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some("two"));
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn get_refreshes_recency() {
+        let cache = Cache::with_capacity(2);
+        cache.set(1, "one", None);
+        cache.set(2, "two", None);
+
+        // touch key 1 so key 2 becomes the least-recently-used entry
+        cache.get(&1);
+        cache.set(3, "three", None);
+
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn get_or_insert_with_returns_existing_value() {
+        let cache = Cache::new(Some(Duration::from_secs(2)));
+        cache.set(KEY, VALUE, None);
+
+        let value = cache.get_or_insert_with(KEY, || "NEW_VALUE", None);
+        assert_eq!(value, VALUE);
+    }
+
+    #[test]
+    fn get_or_insert_with_computes_value_when_absent() {
+        let cache: Cache<i8, &str> = Cache::new(None);
+        let value = cache.get_or_insert_with(KEY, || VALUE, None);
+        assert_eq!(value, VALUE);
+        assert_eq!(cache.get(&KEY), Some(VALUE));
+    }
+
+    #[test]
+    fn get_or_insert_with_computes_value_when_expired() {
+        let cache = Cache::new(Some(Duration::from_secs(0)));
+        cache.set(KEY, VALUE, None);
+
+        let value = cache.get_or_insert_with(KEY, || "NEW_VALUE", None);
+        assert_eq!(value, "NEW_VALUE");
+    }
+
+    #[test]
+    fn get_or_insert_with_respects_capacity() {
+        let cache = Cache::with_capacity(2);
+        cache.get_or_insert_with(1, || "one", None);
+        cache.get_or_insert_with(2, || "two", None);
+        cache.get_or_insert_with(3, || "three", None);
+
+        assert_eq!(cache.items.len(), 2);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some("two"));
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn get_or_insert_with_collapses_concurrent_misses() {
+        let cache = Arc::new(Cache::new(None));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                let calls = Arc::clone(&calls);
+                thread::spawn(move || {
+                    cache.get_or_insert_with(
+                        KEY,
+                        || {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            VALUE
+                        },
+                        None,
+                    )
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), VALUE);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn try_get_or_insert_with_propagates_error() {
+        let cache: Cache<i8, &str> = Cache::new(None);
+        let result: Result<&str, &str> =
+            cache.try_get_or_insert_with(KEY, || Err("boom"), None);
+
+        assert_eq!(result, Err("boom"));
+        assert_eq!(cache.get(&KEY), None);
+    }
+
+    #[test]
+    fn try_get_or_insert_with_inserts_on_success() {
+        let cache: Cache<i8, &str> = Cache::new(None);
+        let result: Result<&str, &str> = cache.try_get_or_insert_with(KEY, || Ok(VALUE), None);
+
+        assert_eq!(result, Ok(VALUE));
+        assert_eq!(cache.get(&KEY), Some(VALUE));
+    }
+
+    #[test]
+    fn get_with_expiry_returns_value_and_expiry() {
+        let cache = Cache::new(Some(Duration::from_secs(2)));
+        cache.set(KEY, VALUE, None);
+
+        match cache.get_with_expiry(&KEY) {
+            Some((value, expiry)) => {
+                assert_eq!(value, VALUE);
+                assert!(expiry > std::time::Instant::now());
+            }
+            None => panic!("value was not found in cache"),
+        };
+    }
+
+    #[test]
+    fn get_with_expiry_returns_none_without_expiry() {
+        let cache = Cache::new(None);
+        cache.set(KEY, VALUE, None);
+
+        assert_eq!(cache.get_with_expiry(&KEY), None);
+    }
+
+    #[test]
+    fn sliding_duration_keeps_touched_item_alive() {
+        let cache = Cache::with_sliding_duration(Duration::from_millis(50));
+        cache.set(KEY, VALUE, None);
+
+        for _ in 0..3 {
+            thread::sleep(Duration::from_millis(30));
+            assert_eq!(cache.get(&KEY), Some(VALUE));
+        }
+    }
+
+    #[test]
+    fn sliding_duration_expires_untouched_item() {
+        let cache = Cache::with_sliding_duration(Duration::from_millis(0));
+        cache.set(KEY, VALUE, None);
+        thread::sleep(Duration::from_millis(1));
+
+        assert_eq!(cache.get(&KEY), None);
+    }
+
+    #[test]
+    fn metrics_track_hits_and_misses() {
+        let cache = Cache::new(Some(Duration::from_secs(2)));
+        cache.set(KEY, VALUE, None);
+
+        cache.get(&KEY);
+        cache.get(&KEY);
+        cache.get(&100);
+
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.hit_ratio(), 2.0 / 3.0);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn metrics_track_capacity_evictions() {
+        let cache = Cache::with_capacity(1);
+        cache.set(1, "one", None);
+        cache.set(2, "two", None);
+
+        assert_eq!(cache.evictions(), 1);
+    }
+
+    #[test]
+    fn metrics_track_expiry_evictions() {
+        let cache = Cache::new(Some(Duration::from_secs(0)));
+        cache.set(KEY, VALUE, None);
+        cache.remove_expired();
+
+        assert_eq!(cache.evictions(), 1);
+    }
+
+    #[test]
+    fn reset_metrics_zeroes_counters() {
+        let cache = Cache::new(Some(Duration::from_secs(2)));
+        cache.set(KEY, VALUE, None);
+        cache.get(&KEY);
+        cache.get(&100);
+
+        cache.reset_metrics();
+
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), 0);
+        assert_eq!(cache.evictions(), 0);
+        assert_eq!(cache.hit_ratio(), 0.0);
+    }
+
+    #[derive(Clone)]
+    struct Token {
+        revoked: bool,
+    }
+
+    impl crate::cache::CanExpire for Token {
+        fn is_expired(&self) -> bool {
+            self.revoked
+        }
+    }
+
+    #[test]
+    fn value_expiry_drops_value_reporting_itself_expired() {
+        let cache = Cache::with_value_expiry(Some(Duration::from_secs(2)));
+        cache.set(KEY, Token { revoked: true }, None);
+
+        assert!(cache.get(&KEY).is_none());
+    }
+
+    #[test]
+    fn value_expiry_keeps_value_not_reporting_expired() {
+        let cache = Cache::with_value_expiry(Some(Duration::from_secs(2)));
+        cache.set(KEY, Token { revoked: false }, None);
+
+        assert!(cache.get(&KEY).is_some());
+    }
+
+    #[test]
+    fn value_expiry_still_honours_time_based_expiry() {
+        let cache = Cache::with_value_expiry(Some(Duration::from_secs(0)));
+        cache.set(KEY, Token { revoked: false }, None);
+
+        assert!(cache.get(&KEY).is_none());
+    }
+
+    #[test]
+    fn eviction_listener_fires_on_replace() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_eviction_listener(Some(Duration::from_secs(2)), {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(KEY, VALUE, None);
+        cache.set(KEY, "NEW_VALUE", None);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(KEY, VALUE, EvictionCause::Replaced)]
+        );
+    }
+
+    #[test]
+    fn eviction_listener_fires_on_remove() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_eviction_listener(Some(Duration::from_secs(2)), {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(KEY, VALUE, None);
+        cache.remove(&KEY);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(KEY, VALUE, EvictionCause::Removed)]
+        );
+    }
+
+    #[test]
+    fn eviction_listener_fires_on_remove_expired() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_eviction_listener(Some(Duration::from_secs(0)), {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(KEY, VALUE, None);
+        cache.remove_expired();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(KEY, VALUE, EvictionCause::Expired)]
+        );
+    }
+
+    #[test]
+    fn eviction_listener_fires_when_get_or_insert_with_displaces_an_expired_value() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_eviction_listener(Some(Duration::from_secs(0)), {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(KEY, VALUE, None);
+        cache.get_or_insert_with(KEY, || "NEW_VALUE", None);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(KEY, VALUE, EvictionCause::Expired)]
+        );
+        assert_eq!(cache.evictions(), 1);
+    }
+
+    #[test]
+    fn eviction_listener_fires_on_clear() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_eviction_listener(Some(Duration::from_secs(2)), {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(KEY, VALUE, None);
+        cache.clear();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(KEY, VALUE, EvictionCause::Cleared)]
+        );
+    }
+
+    #[test]
+    fn eviction_listener_fires_on_capacity_eviction() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let cache = Cache::with_capacity_and_listener(1, None, {
+            let events = Arc::clone(&events);
+            Box::new(move |key, value, cause| events.lock().unwrap().push((*key, value, cause)))
+        });
+
+        cache.set(1, "one", None);
+        cache.set(2, "two", None);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(1, "one", EvictionCause::Capacity)]
+        );
+    }
+
+    #[test]
+    fn remove_expired_uses_live_expiry_for_stale_bucket_entries() {
+        let cache = Cache::new(Some(Duration::from_secs(0)));
+        cache.set(KEY, VALUE, None);
+        // re-set before the sweep with a duration long enough to outlive it; the stale bucket
+        // entry from the first `set` must not cause this live, non-expired item to be removed.
+        cache.set(KEY, VALUE, Some(Duration::from_secs(2)));
+        cache.remove_expired();
+
+        assert_eq!(cache.get(&KEY), Some(VALUE));
+    }
+
+    #[test]
+    fn remove_expired_sweeps_value_only_expiry() {
+        let cache = Cache::with_value_expiry(None);
+        cache.set(KEY, Token { revoked: true }, None);
+        cache.remove_expired();
+
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn remove_expired_sweeps_value_expiry_ahead_of_its_time_based_duration() {
+        let cache = Cache::with_value_expiry(Some(Duration::from_secs(60)));
+        cache.set(KEY, Token { revoked: true }, None);
+        cache.remove_expired();
+
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn spawn_janitor_sweeps_expired_items_in_the_background() {
+        let cache = Arc::new(Cache::new(Some(Duration::from_millis(10))));
+        cache.set(KEY, VALUE, None);
+
+        Cache::spawn_janitor(Arc::clone(&cache), Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(cache.items.len(), 0);
+    }
 }
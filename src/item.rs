@@ -1,24 +1,66 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
-#[derive(Clone)]
 pub struct Item<T> {
     pub object: T,
-    expiry: Option<Instant>,
+    duration: Option<Duration>,
+    expiry: Mutex<Option<Instant>>,
+    last_access: AtomicU64,
 }
 
 impl<T> Item<T> {
-    // Creates a new cache item.
-    pub fn new(object: T, item_duration: Option<Duration>) -> Self {
+    // Creates a new cache item, stamped with the given access tick.
+    pub fn new(object: T, item_duration: Option<Duration>, last_access: u64) -> Self {
         let expiry = item_duration.map(|duration| Instant::now() + duration);
-        Item { object, expiry }
+        Item {
+            object,
+            duration: item_duration,
+            expiry: Mutex::new(expiry),
+            last_access: AtomicU64::new(last_access),
+        }
     }
 
     // Returns true if the item has expired.
     pub fn expired(&self) -> bool {
-        self.expiry
+        self.expiry()
             .map(|expiry| expiry < Instant::now())
             .unwrap_or(false)
     }
+
+    /// Returns the instant this item will expire at, or `None` if it does not expire.
+    pub fn expiry(&self) -> Option<Instant> {
+        *self.expiry.lock().unwrap()
+    }
+
+    // Resets expiry to `duration` from now, used by sliding-expiration caches to keep
+    // frequently accessed items alive.
+    pub fn slide_expiry(&self) {
+        if let Some(duration) = self.duration {
+            *self.expiry.lock().unwrap() = Some(Instant::now() + duration);
+        }
+    }
+
+    // Returns the tick this item was last accessed at.
+    pub fn last_access(&self) -> u64 {
+        self.last_access.load(Ordering::Relaxed)
+    }
+
+    // Records that the item was accessed at the given tick.
+    pub fn touch(&self, tick: u64) {
+        self.last_access.store(tick, Ordering::Relaxed);
+    }
+}
+
+impl<T: Clone> Clone for Item<T> {
+    fn clone(&self) -> Self {
+        Item {
+            object: self.object.clone(),
+            duration: self.duration,
+            expiry: Mutex::new(self.expiry()),
+            last_access: AtomicU64::new(self.last_access()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -32,14 +74,14 @@ mod tests {
 
     #[async_std::test]
     async fn not_expired_when_duration_is_none() {
-        let item = Item::new(OBJECT, None);
-        assert_eq!(item.expired(), false);
+        let item = Item::new(OBJECT, None, 0);
+        assert!(!item.expired());
     }
 
     #[async_std::test]
     async fn expired_when_duration_is_zero() {
-        let item = Item::new(OBJECT, Some(Duration::new(0, 0)));
+        let item = Item::new(OBJECT, Some(Duration::new(0, 0)), 0);
         task::sleep(Duration::from_millis(1)).await;
-        assert_eq!(item.expired(), true);
+        assert!(item.expired());
     }
 }
@@ -10,13 +10,10 @@
 //! const KEY: i8 = 0;
 //! const VALUE: &str = "VALUE";
 //!
-//! # #[async_std::main]
-//! # async fn main() {
-//!    let cache = Cache::new(Some(Duration::from_secs(2 * 60 * 60)));
-//!    cache.set(KEY, VALUE, None).await;
+//! let cache = Cache::new(Some(Duration::from_secs(2 * 60 * 60)));
+//! cache.set(KEY, VALUE, None);
 //!
-//!    println!("{}", cache.get(&KEY).await.unwrap())
-//! }
+//! println!("{}", cache.get(&KEY).unwrap())
 //! ```
 
 mod item;